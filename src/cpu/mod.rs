@@ -6,13 +6,14 @@ use std::io::{BufRead,BufReader};
 
 use sysconf::{sysconf,SysconfVariable};
 
-use super::{Result,Error};
+use super::{Result,Error,FromRead,FromBufRead};
 
 /// Details about current CPU time utilization.
 ///
 /// This object provides insight into the amount of time, measured in USER_HZ, that
 /// the system spent in various modes. These are hertz counters and not necessarily
 /// useful on their own, but against itself over some duration.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, PartialEq)]
 pub struct CpuTimes {
     /// Time spent in user mode
@@ -44,6 +45,41 @@ pub struct CpuTimes {
 }
 
 
+/// Percentage breakdown of CPU time spent in each mode between two
+/// `CpuTimes` snapshots, along with an overall busy percentage.
+///
+/// Each field is a percentage (0-100) of the total time elapsed between
+/// the two snapshots that was spent in that mode.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq)]
+pub struct CpuUtilization {
+    /// Percentage of time spent in user mode
+    pub user: f64,
+    /// Percentage of time spent in user mode with low priority (nice)
+    pub nice: f64,
+    /// Percentage of time spent in system mode
+    pub system: f64,
+    /// Percentage of time spent idle
+    pub idle: f64,
+    /// Percentage of time spent waiting for I/O to complete
+    pub iowait: f64,
+    /// Percentage of time spent servicing interrupts
+    pub irq: f64,
+    /// Percentage of time spent servicing softirqs
+    pub softirq: f64,
+    /// Percentage of time spent in other operating systems when running
+    /// in a virtualized environment.
+    pub steal: f64,
+    /// Percentage of time spent running a virtualized CPU for a guest
+    /// operating system
+    pub guest: f64,
+    /// Percentage of time spent running a niced guest virtualized CPU
+    /// for a guest operating system
+    pub guest_nice: f64,
+    /// Overall percentage of time spent not idle (100 - idle - iowait)
+    pub usage: f64,
+}
+
 impl CpuTimes {
 
     fn from_line(line: &str) -> Result<CpuTimes> {
@@ -90,27 +126,13 @@ impl CpuTimes {
     /// );
     /// ```
     pub fn total() -> Result<CpuTimes> {
-        CpuTimes::total_from_path(Path::new("/proc/stat"))
-    }
-
-    fn total_from_path(stat_path: &Path) -> Result<CpuTimes> {
-        let stat_file = try!(File::open(stat_path).map_err(Error::IoError));
-        CpuTimes::total_from_file(&stat_file)
+        CpuTimes::from_file("/proc/stat")
     }
 
     /// Similar to `CpuTimes::total` but allows you to pass in an existing
     /// `File` to /proc/stat. This method expects the file cursor to be at 0.
     pub fn total_from_file(stat_file: &File) -> Result<CpuTimes> {
-        let reader = BufReader::with_capacity(2048, stat_file);
-
-        let line = match reader.lines().next() {
-            Some(Ok(line)) => line,
-            _ => return Err(Error::RuntimeError(
-                "Expected cpu line but none found.".into()
-            )),
-        };
-
-        CpuTimes::from_line(&line)
+        CpuTimes::from_read(stat_file)
     }
 
     /// Get a `Vec` of `CpuTimes` objects, one for each core.
@@ -162,6 +184,71 @@ impl CpuTimes {
 
         Ok(cpus)
     }
+
+    /// Compute the percentage of time spent in each mode between this
+    /// (later) snapshot and an earlier one.
+    ///
+    /// `guest` and `guest_nice` are already included in `user` and `nice`
+    /// respectively by the kernel, so they are not added again when
+    /// computing the total used to derive the percentages.
+    ///
+    /// ```no_run
+    /// use procure::cpu::CpuTimes;
+    ///
+    /// let earlier = CpuTimes::total().unwrap();
+    /// // ...sleep for a sampling interval...
+    /// let later = CpuTimes::total().unwrap();
+    ///
+    /// let utilization = later.utilization_since(&earlier);
+    /// println!("CPU usage: {:.1}%", utilization.usage);
+    /// ```
+    pub fn utilization_since(&self, earlier: &CpuTimes) -> CpuUtilization {
+        let user = self.user.saturating_sub(earlier.user);
+        let nice = self.nice.saturating_sub(earlier.nice);
+        let system = self.system.saturating_sub(earlier.system);
+        let idle = self.idle.saturating_sub(earlier.idle);
+        let iowait = self.iowait.saturating_sub(earlier.iowait);
+        let irq = self.irq.saturating_sub(earlier.irq);
+        let softirq = self.softirq.saturating_sub(earlier.softirq);
+        let steal = self.steal.saturating_sub(earlier.steal);
+        let guest = self.guest.saturating_sub(earlier.guest);
+        let guest_nice = self.guest_nice.saturating_sub(earlier.guest_nice);
+
+        // guest/guest_nice are already folded into user/nice by the kernel,
+        // so they're excluded here to avoid double-counting the total.
+        let total = user + nice + system + idle + iowait + irq + softirq + steal;
+        let idle_all = idle + iowait;
+        let busy = total.saturating_sub(idle_all);
+
+        let pct = |delta: u64| if total == 0 { 0.0 } else { 100.0 * delta as f64 / total as f64 };
+
+        CpuUtilization {
+            user: pct(user),
+            nice: pct(nice),
+            system: pct(system),
+            idle: pct(idle),
+            iowait: pct(iowait),
+            irq: pct(irq),
+            softirq: pct(softirq),
+            steal: pct(steal),
+            guest: pct(guest),
+            guest_nice: pct(guest_nice),
+            usage: pct(busy),
+        }
+    }
+}
+
+impl FromBufRead for CpuTimes {
+    fn from_buf_read<R: BufRead>(reader: R) -> Result<CpuTimes> {
+        let line = match reader.lines().next() {
+            Some(Ok(line)) => line,
+            _ => return Err(Error::RuntimeError(
+                "Expected cpu line but none found.".into()
+            )),
+        };
+
+        CpuTimes::from_line(&line)
+    }
 }
 
 
@@ -174,7 +261,7 @@ mod tests {
     #[test]
     fn test_total() {
         assert_eq!(
-            CpuTimes::total_from_path(Path::new("testdata/stat-0001")).unwrap(),
+            CpuTimes::from_file("testdata/stat-0001").unwrap(),
             CpuTimes {
                 user: 7969864,
                 nice: 6735,
@@ -216,4 +303,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_utilization_since() {
+        let earlier = CpuTimes {
+            user: 100, nice: 0, system: 50, idle: 800, iowait: 20,
+            irq: 0, softirq: 0, steal: 0, guest: 0, guest_nice: 0,
+        };
+        let later = CpuTimes {
+            user: 150, nice: 0, system: 75, idle: 810, iowait: 25,
+            irq: 0, softirq: 0, steal: 0, guest: 0, guest_nice: 0,
+        };
+
+        let utilization = later.utilization_since(&earlier);
+
+        // total = 50 (user) + 25 (system) + 10 (idle) + 5 (iowait) = 90
+        assert_eq!(utilization.user, 100.0 * 50.0 / 90.0);
+        assert_eq!(utilization.system, 100.0 * 25.0 / 90.0);
+        assert_eq!(utilization.idle, 100.0 * 10.0 / 90.0);
+        assert_eq!(utilization.iowait, 100.0 * 5.0 / 90.0);
+        assert_eq!(utilization.usage, 100.0 * 75.0 / 90.0);
+    }
+
+    #[test]
+    fn test_utilization_since_no_elapsed_time() {
+        let snapshot = CpuTimes {
+            user: 100, nice: 0, system: 50, idle: 800, iowait: 20,
+            irq: 0, softirq: 0, steal: 0, guest: 0, guest_nice: 0,
+        };
+
+        let utilization = snapshot.utilization_since(&snapshot);
+
+        assert_eq!(utilization.usage, 0.0);
+    }
+
 }
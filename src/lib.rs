@@ -6,14 +6,24 @@
 // Externs
 extern crate sysconf;
 
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+
 
 // Imports
-use std::io;
+use std::io::{self, Read, BufRead, BufReader};
 use std::result;
 use std::num::ParseIntError;
+use std::fs::File;
+use std::path::Path;
 
 // Exports
 pub mod cpu;
+pub mod mem;
+pub mod net;
 pub mod process;
 
 /// Custom Result type many `procure` methods return
@@ -27,3 +37,31 @@ pub enum Error {
     ParseError(ParseIntError),
 }
 
+/// Parse `Self` out of a single line or a small, whole-file buffer, such
+/// as `/proc/stat` or `/proc/meminfo`.
+///
+/// Implementing this (rather than `FromRead`) is usually all a metric
+/// type needs to do, since the blanket impl below takes care of wrapping
+/// arbitrary readers in a `BufReader`.
+pub trait FromBufRead: Sized {
+    fn from_buf_read<R: BufRead>(reader: R) -> Result<Self>;
+}
+
+/// Parse `Self` out of any byte source: an in-memory buffer, a socket, a
+/// test fixture, or a real `File`.
+pub trait FromRead: Sized {
+    fn from_read<R: Read>(reader: R) -> Result<Self>;
+
+    /// Convenience wrapper that opens `path` and parses it with
+    /// `from_read`.
+    fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = try!(File::open(path).map_err(Error::IoError));
+        Self::from_read(file)
+    }
+}
+
+impl<T: FromBufRead> FromRead for T {
+    fn from_read<R: Read>(reader: R) -> Result<Self> {
+        T::from_buf_read(BufReader::new(reader))
+    }
+}
@@ -0,0 +1,135 @@
+//! Memory Metrics
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufRead;
+
+use super::{Result,Error,FromRead,FromBufRead};
+
+/// Details about current memory and swap usage.
+///
+/// This object provides insight into the amount of physical memory and
+/// swap space in use, all normalized to bytes even though the kernel
+/// reports most `/proc/meminfo` entries in kB.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq)]
+pub struct MemInfo {
+    /// Total usable physical memory
+    pub mem_total: u64,
+    /// Physical memory currently unused
+    pub mem_free: u64,
+    /// Estimate of memory available for starting new applications
+    /// without swapping
+    pub mem_available: u64,
+    /// Memory used for block device buffers
+    pub buffers: u64,
+    /// Memory used for the page cache
+    pub cached: u64,
+    /// Total swap space
+    pub swap_total: u64,
+    /// Swap space currently unused
+    pub swap_free: u64,
+    /// Memory used by tmpfs and shared memory segments
+    pub shmem: u64,
+    /// Any `/proc/meminfo` keys not surfaced above. Values reported in kB
+    /// are normalized to bytes; unitless counts (e.g. `HugePages_Total`)
+    /// are stored as-is. Keeps `MemInfo` forward-compatible with newer
+    /// kernels.
+    pub extra: HashMap<String, u64>,
+}
+
+
+impl MemInfo {
+
+    fn from_lines<I: Iterator<Item=::std::io::Result<String>>>(lines: I) -> Result<MemInfo> {
+        let mut fields: HashMap<String, u64> = HashMap::new();
+
+        for line in lines {
+            let line = try!(line.map_err(Error::IoError));
+
+            let mut parts = line.splitn(2, ':');
+            let key = match parts.next() {
+                Some(key) => key.trim(),
+                None => continue,
+            };
+            let value = match parts.next() {
+                Some(value) => value.trim(),
+                None => continue,
+            };
+
+            // Only kB values are scaled; unitless counts are kept as-is.
+            let (number, scale) = if value.ends_with("kB") {
+                (value.trim_right_matches("kB").trim(), 1024)
+            } else {
+                (value, 1)
+            };
+
+            if let Ok(n) = number.parse::<u64>() {
+                fields.insert(key.to_string(), n * scale);
+            }
+        }
+
+        macro_rules! take {
+            ($key:expr) => { fields.remove($key).unwrap_or(0) }
+        }
+
+        Ok(MemInfo {
+            mem_total: take!("MemTotal"),
+            mem_free: take!("MemFree"),
+            mem_available: take!("MemAvailable"),
+            buffers: take!("Buffers"),
+            cached: take!("Cached"),
+            swap_total: take!("SwapTotal"),
+            swap_free: take!("SwapFree"),
+            shmem: take!("Shmem"),
+            extra: fields,
+        })
+    }
+
+    /// Get a `MemInfo` object filled with current memory and swap usage.
+    ///
+    /// ```no_run
+    /// use procure::mem::MemInfo;
+    ///
+    /// let meminfo = MemInfo::total().unwrap();
+    /// println!("{} bytes free", meminfo.mem_free);
+    /// ```
+    pub fn total() -> Result<MemInfo> {
+        MemInfo::from_file("/proc/meminfo")
+    }
+
+    /// Similar to `MemInfo::total` but allows you to pass in an existing
+    /// `File` to /proc/meminfo. This method expects the file cursor to be
+    /// at 0.
+    pub fn total_from_file(meminfo_file: &File) -> Result<MemInfo> {
+        MemInfo::from_read(meminfo_file)
+    }
+}
+
+impl FromBufRead for MemInfo {
+    fn from_buf_read<R: BufRead>(reader: R) -> Result<MemInfo> {
+        MemInfo::from_lines(reader.lines())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_total() {
+        let meminfo = MemInfo::from_file("testdata/meminfo-0001").unwrap();
+
+        assert_eq!(meminfo.mem_total, 16308446 * 1024);
+        assert_eq!(meminfo.mem_free, 9725088 * 1024);
+        assert_eq!(meminfo.mem_available, 14954616 * 1024);
+        assert_eq!(meminfo.buffers, 303328 * 1024);
+        assert_eq!(meminfo.cached, 4613920 * 1024);
+        assert_eq!(meminfo.swap_total, 0);
+        assert_eq!(meminfo.swap_free, 0);
+        assert_eq!(meminfo.shmem, 17432 * 1024);
+        assert_eq!(meminfo.extra.get("HugePages_Total"), Some(&0));
+    }
+
+}
@@ -0,0 +1,182 @@
+//! Network Interface Metrics
+
+use std::path::Path;
+use std::fs::File;
+use std::io::{BufRead,BufReader};
+use std::time::Duration;
+
+use super::{Result,Error};
+
+/// Receive and transmit counters for a single network interface, parsed
+/// from `/proc/net/dev`.
+///
+/// These are cumulative counters since the interface came up, and are
+/// most useful diffed against a later snapshot of the same interface
+/// with [`throughput_since`](#method.throughput_since).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq)]
+pub struct InterfaceStats {
+    /// Interface name, e.g. `eth0`
+    pub name: String,
+    /// Bytes received
+    pub rx_bytes: u64,
+    /// Packets received
+    pub rx_packets: u64,
+    /// Receive errors
+    pub rx_errs: u64,
+    /// Packets dropped on receive
+    pub rx_drop: u64,
+    /// Bytes transmitted
+    pub tx_bytes: u64,
+    /// Packets transmitted
+    pub tx_packets: u64,
+    /// Transmit errors
+    pub tx_errs: u64,
+    /// Packets dropped on transmit
+    pub tx_drop: u64,
+}
+
+/// Receive and transmit throughput for an interface, computed by
+/// [`InterfaceStats::throughput_since`](struct.InterfaceStats.html#method.throughput_since).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq)]
+pub struct InterfaceThroughput {
+    /// Bytes received per second
+    pub rx_bytes_per_sec: f64,
+    /// Bytes transmitted per second
+    pub tx_bytes_per_sec: f64,
+}
+
+impl InterfaceStats {
+
+    fn from_line(line: &str) -> Result<InterfaceStats> {
+        let mut halves = line.splitn(2, ':');
+
+        let name = try!(halves.next().ok_or_else(|| Error::RuntimeError(
+            "Expected interface name in /proc/net/dev line.".into()
+        ))).trim().to_string();
+
+        let counters: Vec<_> = try!(halves.next().ok_or_else(|| Error::RuntimeError(
+            "Expected counters after interface name in /proc/net/dev line.".into()
+        ))).split_whitespace()
+            .map(|elem| elem.parse::<u64>().unwrap_or(0))
+            .collect();
+
+        macro_rules! field {
+            ($idx:expr) => { *try!(counters.get($idx).ok_or_else(|| Error::RuntimeError(
+                format!("Expected field {} in /proc/net/dev line.", $idx)
+            ))) }
+        }
+
+        Ok(InterfaceStats {
+            name: name,
+            rx_bytes: field!(0),
+            rx_packets: field!(1),
+            rx_errs: field!(2),
+            rx_drop: field!(3),
+            tx_bytes: field!(8),
+            tx_packets: field!(9),
+            tx_errs: field!(10),
+            tx_drop: field!(11),
+        })
+    }
+
+    /// Get a `Vec` of `InterfaceStats`, one for each network interface.
+    ///
+    /// ```no_run
+    /// use procure::net::InterfaceStats;
+    ///
+    /// let interfaces = InterfaceStats::all().unwrap();
+    /// ```
+    pub fn all() -> Result<Vec<InterfaceStats>> {
+        InterfaceStats::all_from_path(Path::new("/proc/net/dev"))
+    }
+
+    fn all_from_path(dev_path: &Path) -> Result<Vec<InterfaceStats>> {
+        let dev_file = try!(File::open(dev_path).map_err(Error::IoError));
+        InterfaceStats::all_from_file(&dev_file)
+    }
+
+    /// Similar to `InterfaceStats::all` but allows you to pass in an
+    /// existing `File` to /proc/net/dev. This method expects the file
+    /// cursor to be at 0.
+    pub fn all_from_file(dev_file: &File) -> Result<Vec<InterfaceStats>> {
+        let reader = BufReader::with_capacity(2048, dev_file);
+        let mut interfaces = Vec::new();
+
+        // The first two lines are a (wrapped) header, not data.
+        for line in reader.lines().skip(2) {
+            let line = try!(line.map_err(Error::IoError));
+            interfaces.push(try!(InterfaceStats::from_line(&line)));
+        }
+
+        Ok(interfaces)
+    }
+
+    /// Compute receive/transmit throughput between this (later) snapshot
+    /// and an earlier one of the same interface, given the `Duration`
+    /// that elapsed between the two samples.
+    pub fn throughput_since(&self, earlier: &InterfaceStats, elapsed: Duration) -> InterfaceThroughput {
+        let secs = elapsed.as_secs() as f64 + (elapsed.subsec_nanos() as f64 / 1_000_000_000.0);
+
+        let rx_bytes = self.rx_bytes.saturating_sub(earlier.rx_bytes);
+        let tx_bytes = self.tx_bytes.saturating_sub(earlier.tx_bytes);
+
+        let rate = |delta: u64| if secs <= 0.0 { 0.0 } else { delta as f64 / secs };
+
+        InterfaceThroughput {
+            rx_bytes_per_sec: rate(rx_bytes),
+            tx_bytes_per_sec: rate(tx_bytes),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::path::Path;
+    use std::time::Duration;
+
+    #[test]
+    fn test_all() {
+        let interfaces = InterfaceStats::all_from_path(Path::new("testdata/net-dev-0001")).unwrap();
+
+        assert_eq!(
+            interfaces,
+            vec![
+                InterfaceStats {
+                    name: "lo".into(),
+                    rx_bytes: 7665, rx_packets: 84, rx_errs: 0, rx_drop: 0,
+                    tx_bytes: 7665, tx_packets: 84, tx_errs: 0, tx_drop: 0,
+                },
+                InterfaceStats {
+                    name: "eth0".into(),
+                    rx_bytes: 438968081, rx_packets: 330743, rx_errs: 0, rx_drop: 0,
+                    tx_bytes: 3146518, tx_packets: 26103, tx_errs: 0, tx_drop: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_throughput_since() {
+        let earlier = InterfaceStats {
+            name: "eth0".into(),
+            rx_bytes: 1000, rx_packets: 10, rx_errs: 0, rx_drop: 0,
+            tx_bytes: 500, tx_packets: 5, tx_errs: 0, tx_drop: 0,
+        };
+        let later = InterfaceStats {
+            name: "eth0".into(),
+            rx_bytes: 3000, rx_packets: 20, rx_errs: 0, rx_drop: 0,
+            tx_bytes: 1500, tx_packets: 10, tx_errs: 0, tx_drop: 0,
+        };
+
+        let throughput = later.throughput_since(&earlier, Duration::from_secs(2));
+
+        assert_eq!(throughput.rx_bytes_per_sec, 1000.0);
+        assert_eq!(throughput.tx_bytes_per_sec, 500.0);
+    }
+
+}
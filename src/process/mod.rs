@@ -1,8 +1,14 @@
 //! Process Metrics
 
 use std::fs;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 use std::iter::Iterator;
 
+use sysconf::{sysconf, SysconfVariable};
+
+use super::{Result, Error};
+
 fn pids_from_path(proc_path: &str) -> impl Iterator<Item=i32> {
     fs::read_dir(proc_path).unwrap()
         // Process directories might have gone away since
@@ -19,6 +25,128 @@ pub fn pids() -> impl Iterator<Item=i32> {
     pids_from_path("/proc")
 }
 
+/// Iterator over a `Process` for every pid currently visible under `/proc`.
+///
+/// Processes that disappear between listing `/proc` and reading their
+/// `stat`/`statm` files are silently skipped.
+pub fn processes() -> impl Iterator<Item=Process> {
+    pids().filter_map(|pid| Process::new(pid).ok())
+}
+
+
+/// Per-process CPU time, memory, and scheduling details read from
+/// `/proc/[pid]/stat` and `/proc/[pid]/statm`.
+///
+/// `utime` and `stime` are hertz counters, like `cpu::CpuTimes`, and are
+/// most useful diffed against a later snapshot of the same pid.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq)]
+pub struct Process {
+    /// Process ID
+    pub pid: i32,
+    /// The filename of the executable, as it appears in `/proc/[pid]/stat`
+    pub comm: String,
+    /// Current process state, e.g. `R` (running), `S` (sleeping)
+    pub state: char,
+    /// Parent process ID
+    pub ppid: i32,
+    /// Time this process has spent scheduled in user mode, in USER_HZ
+    pub utime: u64,
+    /// Time this process has spent scheduled in kernel mode, in USER_HZ
+    pub stime: u64,
+    /// Number of threads in this process
+    pub num_threads: i64,
+    /// Time the process started after system boot, in USER_HZ
+    pub starttime: u64,
+    /// Virtual memory size, in bytes
+    pub vsize: u64,
+    /// Resident set size, in pages
+    pub rss: u64,
+}
+
+impl Process {
+
+    fn from_stat_line(line: &str) -> Result<Process> {
+        let open_paren = try!(line.find('(').ok_or_else(|| Error::RuntimeError(
+            "Expected '(' before comm in stat line.".into()
+        )));
+        let close_paren = try!(line.rfind(')').ok_or_else(|| Error::RuntimeError(
+            "Expected ')' after comm in stat line.".into()
+        )));
+
+        let pid = try!(line[..open_paren].trim().parse::<i32>().map_err(Error::ParseError));
+        let comm = line[open_paren + 1..close_paren].to_string();
+
+        let rest: Vec<_> = line[close_paren + 1..].split_whitespace().collect();
+
+        let state = try!(rest.get(0).and_then(|s| s.chars().next()).ok_or_else(|| Error::RuntimeError(
+            "Expected state field in stat line.".into()
+        )));
+
+        macro_rules! field {
+            ($idx:expr) => {
+                try!(try!(rest.get($idx).ok_or_else(|| Error::RuntimeError(
+                    format!("Expected field {} in stat line.", $idx)
+                ))).parse().map_err(Error::ParseError))
+            }
+        }
+
+        Ok(Process {
+            pid: pid,
+            comm: comm,
+            state: state,
+            ppid: field!(1),
+            utime: field!(11),
+            stime: field!(12),
+            num_threads: field!(17),
+            starttime: field!(19),
+            vsize: field!(20),
+            // rss is overwritten with the statm value by `from_proc_path`
+            rss: 0,
+        })
+    }
+
+    fn from_statm_line(line: &str) -> Result<u64> {
+        line.split_whitespace()
+            .nth(1)
+            .ok_or_else(|| Error::RuntimeError(
+                "Expected resident field in statm line.".into()
+            ))
+            .and_then(|field| field.parse::<u64>().map_err(Error::ParseError))
+    }
+
+    fn from_proc_path(proc_path: &str) -> Result<Process> {
+        let stat_line = try!(read_first_line(&format!("{}/stat", proc_path)));
+        let statm_line = try!(read_first_line(&format!("{}/statm", proc_path)));
+
+        let mut process = try!(Process::from_stat_line(&stat_line));
+        process.rss = try!(Process::from_statm_line(&statm_line));
+
+        Ok(process)
+    }
+
+    /// Read process details for the given pid from `/proc/[pid]/stat` and
+    /// `/proc/[pid]/statm`.
+    pub fn new(pid: i32) -> Result<Process> {
+        Process::from_proc_path(&format!("/proc/{}", pid))
+    }
+
+    /// Resident set size, converted from pages to bytes using the system
+    /// page size.
+    pub fn rss_bytes(&self) -> u64 {
+        let page_size = sysconf(SysconfVariable::ScPagesize).unwrap_or(0) as u64;
+        self.rss * page_size
+    }
+}
+
+fn read_first_line(path: &str) -> Result<String> {
+    let file = try!(File::open(path).map_err(Error::IoError));
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    try!(reader.read_line(&mut line).map_err(Error::IoError));
+    Ok(line)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -30,4 +158,29 @@ mod tests {
         pids.sort();
         assert_eq!(pids, vec![1, 16018, 24064, 24126]);
     }
+
+    #[test]
+    fn test_from_proc_path() {
+        let process = Process::from_proc_path("testdata/proc/24064").unwrap();
+        assert_eq!(process.pid, 24064);
+        assert_eq!(process.comm, "bash");
+        assert_eq!(process.state, 'S');
+        assert_eq!(process.ppid, 24063);
+        assert_eq!(process.utime, 12);
+        assert_eq!(process.stime, 4);
+        assert_eq!(process.num_threads, 1);
+        assert_eq!(process.starttime, 8692);
+        assert_eq!(process.vsize, 123265024);
+        assert_eq!(process.rss, 1755);
+    }
+
+    #[test]
+    fn test_from_stat_line_with_parens_in_comm() {
+        let line = "24064 (some (weird) proc) S 24063 24064 24064 0 -1 4194304 \
+                     123 0 0 0 12 4 0 0 20 0 1 0 8692 123265024 1755 \
+                     18446744073709551615 1 1 0 0 0 0 0 0 0 0 0 0 17 2 0 0 0 0 0";
+        let process = Process::from_stat_line(line).unwrap();
+        assert_eq!(process.comm, "some (weird) proc");
+        assert_eq!(process.ppid, 24063);
+    }
 }